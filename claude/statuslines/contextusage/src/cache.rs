@@ -0,0 +1,102 @@
+use crate::pricing::TokenTotals;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Per-transcript state we persist between invocations so we only have to
+/// parse the lines appended since the last run.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Cache {
+    /// Byte offset into the transcript we have already consumed.
+    pub offset: u64,
+    /// Size the transcript was the last time we read it, used to detect
+    /// truncation/rotation (a file that shrank can't just be resumed).
+    pub len: u64,
+    /// Last token usage we computed, carried forward when no new line
+    /// updates it.
+    pub current_token_usage: u64,
+    /// Token counts accumulated across every transcript entry seen so far,
+    /// used for cost estimation.
+    #[serde(default)]
+    pub totals: TokenTotals,
+    /// Locally-estimated tokens for entries seen since the last one that
+    /// carried a real usage block, reset whenever a real usage count
+    /// arrives. Only grows while token estimation is enabled; a value left
+    /// from an earlier run is carried forward unchanged while it is disabled.
+    #[serde(default)]
+    pub estimated_tail_tokens: u64,
+}
+
+fn cache_dir() -> PathBuf {
+    std::env::temp_dir().join("claude-contextusage-cache")
+}
+
+fn cache_path(transcript_path: &Path) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(transcript_path.to_string_lossy().as_bytes());
+    let hash = hasher.finalize();
+    cache_dir().join(format!("{:x}.json", hash))
+}
+
+impl Cache {
+    /// Loads the cache for `transcript_path`, if one exists and the
+    /// transcript hasn't shrunk since it was written (which would mean the
+    /// transcript was truncated or rotated out from under us).
+    pub fn load(transcript_path: &Path, current_len: u64) -> Option<Cache> {
+        let data = fs::read_to_string(cache_path(transcript_path)).ok()?;
+        let cache: Cache = serde_json::from_str(&data).ok()?;
+        if current_len < cache.len {
+            return None;
+        }
+        Some(cache)
+    }
+
+    pub fn save(&self, transcript_path: &Path) -> io::Result<()> {
+        let dir = cache_dir();
+        fs::create_dir_all(&dir)?;
+        let data = serde_json::to_string(self)?;
+        fs::write(cache_path(transcript_path), data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_returns_none_when_transcript_shrank() {
+        let transcript_path = PathBuf::from("/tmp/claude-contextusage-test-shrink.jsonl");
+        let cache = Cache {
+            offset: 100,
+            len: 100,
+            current_token_usage: 42,
+            ..Cache::default()
+        };
+        cache.save(&transcript_path).unwrap();
+
+        assert!(Cache::load(&transcript_path, 50).is_none());
+    }
+
+    #[test]
+    fn load_returns_cache_when_transcript_grew_or_is_unchanged() {
+        let transcript_path = PathBuf::from("/tmp/claude-contextusage-test-grow.jsonl");
+        let cache = Cache {
+            offset: 100,
+            len: 100,
+            current_token_usage: 42,
+            ..Cache::default()
+        };
+        cache.save(&transcript_path).unwrap();
+
+        assert_eq!(Cache::load(&transcript_path, 100).unwrap().current_token_usage, 42);
+        assert_eq!(Cache::load(&transcript_path, 200).unwrap().current_token_usage, 42);
+    }
+
+    #[test]
+    fn load_returns_none_when_no_cache_exists() {
+        let transcript_path = PathBuf::from("/tmp/claude-contextusage-test-missing.jsonl");
+        assert!(Cache::load(&transcript_path, 0).is_none());
+    }
+}
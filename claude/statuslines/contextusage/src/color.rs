@@ -0,0 +1,111 @@
+use crate::config::Palette;
+use owo_colors::{AnsiColors, OwoColorize};
+use std::io::IsTerminal;
+
+/// Whether to emit ANSI/truecolor escapes in the output.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorMode {
+    Always,
+    Never,
+}
+
+/// Downgrades an RGB triple to the nearest basic ANSI color, for terminals
+/// (or users) that don't want truecolor escapes.
+fn nearest_basic(rgb: [u8; 3]) -> AnsiColors {
+    let [r, g, b] = rgb.map(|c| c > 127);
+    match (r, g, b) {
+        (true, true, true) => AnsiColors::White,
+        (true, true, false) => AnsiColors::Yellow,
+        (true, false, true) => AnsiColors::Magenta,
+        (true, false, false) => AnsiColors::Red,
+        (false, true, true) => AnsiColors::Cyan,
+        (false, true, false) => AnsiColors::Green,
+        (false, false, true) => AnsiColors::Blue,
+        (false, false, false) => AnsiColors::Black,
+    }
+}
+
+/// Renders `text` in `rgb`, honoring both `mode` (as resolved by `resolve`) and
+/// `palette` (truecolor vs. the nearest basic ANSI color).
+pub fn paint_rgb(mode: ColorMode, palette: Palette, text: &str, rgb: [u8; 3]) -> String {
+    match mode {
+        ColorMode::Never => text.to_string(),
+        ColorMode::Always => match palette {
+            Palette::Truecolor => text.truecolor(rgb[0], rgb[1], rgb[2]).to_string(),
+            Palette::Plain => text.color(nearest_basic(rgb)).to_string(),
+        },
+    }
+}
+
+/// Resolves the effective color mode from the `--color` CLI argument and the
+/// `NO_COLOR` environment variable, mirroring the convention used by tools
+/// like `ripgrep` and `git`.
+///
+/// `--color` accepts `auto` (the default), `always`, or `never`. `NO_COLOR`
+/// (any non-empty value) forces `never` unless `--color=always` was passed
+/// explicitly, matching the precedence other CLIs give an explicit flag
+/// over the environment.
+pub fn resolve(args: &[String]) -> ColorMode {
+    let requested = args.iter().find_map(|arg| arg.strip_prefix("--color="));
+
+    match requested {
+        Some("always") => ColorMode::Always,
+        Some("never") => ColorMode::Never,
+        _ => {
+            if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+                ColorMode::Never
+            } else if std::io::stdout().is_terminal() {
+                ColorMode::Always
+            } else {
+                ColorMode::Never
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_color_always_overrides_no_color() {
+        std::env::set_var("NO_COLOR", "1");
+        let mode = resolve(&["--color=always".to_string()]);
+        std::env::remove_var("NO_COLOR");
+        assert_eq!(mode, ColorMode::Always);
+    }
+
+    #[test]
+    fn explicit_color_never_wins_outright() {
+        std::env::remove_var("NO_COLOR");
+        assert_eq!(resolve(&["--color=never".to_string()]), ColorMode::Never);
+    }
+
+    #[test]
+    fn empty_no_color_is_not_honored() {
+        std::env::set_var("NO_COLOR", "");
+        let mode = resolve(&[]);
+        std::env::remove_var("NO_COLOR");
+        // With NO_COLOR empty and no explicit flag, the result falls through
+        // to the stdout-is-a-terminal check rather than being forced to
+        // Never by the NO_COLOR branch.
+        let expected = if std::io::stdout().is_terminal() {
+            ColorMode::Always
+        } else {
+            ColorMode::Never
+        };
+        assert_eq!(mode, expected);
+    }
+
+    #[test]
+    fn nearest_basic_downgrades_rgb_to_ansi() {
+        assert_eq!(nearest_basic([255, 0, 0]), AnsiColors::Red);
+        assert_eq!(nearest_basic([0, 0, 0]), AnsiColors::Black);
+        assert_eq!(nearest_basic([255, 255, 255]), AnsiColors::White);
+    }
+
+    #[test]
+    fn paint_rgb_never_passes_text_through_unchanged() {
+        assert_eq!(paint_rgb(ColorMode::Never, Palette::Truecolor, "hi", [255, 0, 0]), "hi");
+    }
+}
@@ -0,0 +1,172 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// A single piece of the statusline, in display order.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Segment {
+    Path,
+    Percentage,
+    Model,
+    Cost,
+    GitBranch,
+}
+
+fn default_segments() -> Vec<Segment> {
+    vec![Segment::Path, Segment::Percentage, Segment::Model, Segment::Cost]
+}
+
+/// Whether threshold colors render as 24-bit truecolor or are downgraded to
+/// the nearest basic ANSI color, for terminals that don't support truecolor.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    Plain,
+    #[default]
+    Truecolor,
+}
+
+/// A context-fill ratio breakpoint: once the ratio exceeds `above`, the
+/// percentage segment renders in `color`.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Threshold {
+    pub above: f64,
+    pub color: [u8; 3],
+}
+
+fn default_thresholds() -> Vec<Threshold> {
+    vec![
+        Threshold {
+            above: 0.7,
+            color: [255, 0, 0],
+        },
+        Threshold {
+            above: 0.5,
+            color: [255, 165, 0],
+        },
+        Threshold {
+            above: 0.3,
+            color: [255, 255, 0],
+        },
+    ]
+}
+
+fn default_color() -> [u8; 3] {
+    [175, 175, 175]
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Theme {
+    #[serde(default = "default_thresholds")]
+    pub thresholds: Vec<Threshold>,
+    /// Color used when the ratio doesn't clear any threshold in `thresholds`.
+    #[serde(default = "default_color")]
+    pub default_color: [u8; 3],
+    #[serde(default)]
+    pub palette: Palette,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            thresholds: default_thresholds(),
+            default_color: default_color(),
+            palette: Palette::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Config {
+    #[serde(default = "default_segments")]
+    pub segments: Vec<Segment>,
+    #[serde(default)]
+    pub theme: Theme,
+    /// Whether to approximate token counts for transcript entries that have
+    /// no real usage block, via a local BPE tokenizer. Off by default since
+    /// it's an approximation for Anthropic models.
+    #[serde(default)]
+    pub estimate_missing_tokens: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            segments: default_segments(),
+            theme: Theme::default(),
+            estimate_missing_tokens: false,
+        }
+    }
+}
+
+impl Theme {
+    /// Returns the color for `ratio`, picking the highest threshold it
+    /// clears (thresholds need not be declared in any particular order in
+    /// the config file).
+    pub fn color_for(&self, ratio: f64) -> [u8; 3] {
+        self.thresholds
+            .iter()
+            .filter(|t| ratio > t.above)
+            .max_by(|a, b| a.above.total_cmp(&b.above))
+            .map(|t| t.color)
+            .unwrap_or(self.default_color)
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
+        if !xdg.is_empty() {
+            return Some(PathBuf::from(xdg).join("claude-contextusage/config.toml"));
+        }
+    }
+    std::env::var("HOME")
+        .ok()
+        .map(|home| PathBuf::from(home).join(".config/claude-contextusage/config.toml"))
+}
+
+/// Loads the statusline config from the standard config path, falling back
+/// to `Config::default()` when the file is absent or fails to parse.
+pub fn load() -> Config {
+    config_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|data| toml::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme(thresholds: Vec<Threshold>) -> Theme {
+        Theme {
+            thresholds,
+            default_color: [1, 2, 3],
+            palette: Palette::default(),
+        }
+    }
+
+    #[test]
+    fn color_for_picks_highest_threshold_cleared_regardless_of_declaration_order() {
+        let theme = theme(vec![
+            Threshold { above: 0.3, color: [1, 0, 0] },
+            Threshold { above: 0.7, color: [3, 0, 0] },
+            Threshold { above: 0.5, color: [2, 0, 0] },
+        ]);
+
+        assert_eq!(theme.color_for(0.8), [3, 0, 0]);
+        assert_eq!(theme.color_for(0.6), [2, 0, 0]);
+        assert_eq!(theme.color_for(0.4), [1, 0, 0]);
+    }
+
+    #[test]
+    fn color_for_falls_back_to_default_color_below_every_threshold() {
+        let theme = theme(vec![Threshold { above: 0.3, color: [1, 0, 0] }]);
+        assert_eq!(theme.color_for(0.1), [1, 2, 3]);
+    }
+
+    #[test]
+    fn color_for_is_strictly_greater_than_at_the_boundary() {
+        let theme = theme(vec![Threshold { above: 0.5, color: [1, 0, 0] }]);
+        assert_eq!(theme.color_for(0.5), [1, 2, 3]);
+    }
+}
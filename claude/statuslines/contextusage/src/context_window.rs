@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+/// Context window size to fall back to for a model we don't recognize.
+const DEFAULT_WINDOW: f64 = 200_000.0;
+
+/// Built-in context window sizes, keyed by `Model::display_name`. Every
+/// currently-shipping model uses the same standard 200k window (`DEFAULT_WINDOW`
+/// already covers them), so there's nothing real to put here yet. Register
+/// a window for a model that actually differs from the default via
+/// `CLAUDE_STATUSLINE_CONTEXT_WINDOW_OVERRIDE`, a path to a JSON file mapping
+/// `display_name` -> window size, without recompiling.
+fn default_windows() -> HashMap<&'static str, f64> {
+    HashMap::new()
+}
+
+/// Loads the built-in context window table, merged with any overrides found
+/// at `CLAUDE_STATUSLINE_CONTEXT_WINDOW_OVERRIDE`. Overrides are best-effort:
+/// a missing or unparsable file is silently ignored.
+pub fn load_table() -> HashMap<String, f64> {
+    let mut table: HashMap<String, f64> = default_windows()
+        .into_iter()
+        .map(|(name, window)| (name.to_string(), window))
+        .collect();
+
+    if let Ok(path) = env::var("CLAUDE_STATUSLINE_CONTEXT_WINDOW_OVERRIDE") {
+        if let Ok(data) = fs::read_to_string(path) {
+            if let Ok(overrides) = serde_json::from_str::<HashMap<String, f64>>(&data) {
+                table.extend(overrides);
+            }
+        }
+    }
+
+    table
+}
+
+/// Looks up the context window for `display_name`, falling back to
+/// `DEFAULT_WINDOW` when the model isn't in `table`.
+pub fn window_for(table: &HashMap<String, f64>, display_name: &str) -> f64 {
+    table.get(display_name).copied().unwrap_or(DEFAULT_WINDOW)
+}
@@ -0,0 +1,43 @@
+use std::sync::OnceLock;
+use tiktoken_rs::{cl100k_base, CoreBPE};
+
+static TOKENIZER: OnceLock<Option<CoreBPE>> = OnceLock::new();
+
+/// Returns the shared BPE tokenizer, compiling it on first use. Building the
+/// tokenizer's merge ranks is the expensive part, so we do it once per
+/// process rather than per transcript entry. `cl100k_base` can fail to
+/// initialize in offline/sandboxed environments, so a failure here just
+/// disables estimation for this run rather than taking down the statusline.
+fn tokenizer() -> Option<&'static CoreBPE> {
+    TOKENIZER.get_or_init(|| cl100k_base().ok()).as_ref()
+}
+
+/// Approximates the token count of `text` with a BPE tokenizer. This is only
+/// an approximation for Anthropic models, which use a different vocabulary,
+/// so it's meant as a fallback for entries that don't carry a real usage
+/// count rather than a substitute for one. Returns 0 if the tokenizer
+/// couldn't be initialized, same as if estimation found nothing to count.
+pub fn estimate_tokens(text: &str) -> u64 {
+    if text.is_empty() {
+        return 0;
+    }
+    match tokenizer() {
+        Some(bpe) => bpe.encode_with_special_tokens(text).len() as u64,
+        None => 0,
+    }
+}
+
+/// Extracts the plain-text content of a transcript message's `content`
+/// field, which may be a bare string or an array of content blocks (text,
+/// tool_use, tool_result, ...). Non-text blocks are ignored.
+pub fn extract_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,26 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Returns the current branch name for the repository containing `cwd`, or
+/// `None` if `cwd` isn't inside a git repository, is in a detached-HEAD
+/// state, or `git` isn't on `PATH`.
+pub fn current_branch(cwd: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(cwd)
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let branch = String::from_utf8(output.stdout).ok()?;
+    let branch = branch.trim();
+    if branch.is_empty() || branch == "HEAD" {
+        None
+    } else {
+        Some(branch.to_string())
+    }
+}
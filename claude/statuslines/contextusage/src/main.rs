@@ -1,11 +1,19 @@
+mod cache;
+mod color;
+mod config;
+mod context_window;
+mod estimator;
+mod git;
+mod pricing;
+
+use cache::Cache;
+use color::ColorMode;
 use owo_colors::OwoColorize;
 use serde::Deserialize;
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Read};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::PathBuf;
 
-const TOKEN_LIMIT: f64 = 200_000.0;
-
 #[derive(Deserialize)]
 struct Input {
     transcript_path: PathBuf,
@@ -26,88 +34,193 @@ struct TranscriptEntry {
 #[derive(Deserialize)]
 struct Message {
     usage: Option<Usage>,
+    #[serde(default)]
+    content: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize)]
 struct Usage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
     #[serde(default)]
     cache_creation_input_tokens: u64,
     #[serde(default)]
     cache_read_input_tokens: u64,
 }
 
+/// Applies `f` to `text` when `mode` is `ColorMode::Always`, otherwise
+/// passes `text` through unchanged. Named-color counterpart of
+/// `color::paint_rgb`, which downgrades to plain text the same way.
+fn paint(mode: ColorMode, text: &str, f: impl FnOnce(&str) -> String) -> String {
+    match mode {
+        ColorMode::Always => f(text),
+        ColorMode::Never => text.to_string(),
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    let color_mode = color::resolve(&args);
+    let config = config::load();
+
     let mut stdin = io::stdin();
     let mut input_data = String::new();
     stdin.read_to_string(&mut input_data)?;
 
     let input: Input = serde_json::from_str(&input_data)?;
 
-    let file = File::open(&input.transcript_path)?;
-    let reader = BufReader::new(file);
+    let mut file = File::open(&input.transcript_path)?;
+    let file_len = file.metadata()?.len();
+
+    let cached = Cache::load(&input.transcript_path, file_len);
+    let mut current_token_usage = cached.as_ref().map(|c| c.current_token_usage).unwrap_or(0);
+    let mut totals = cached.as_ref().map(|c| c.totals).unwrap_or_default();
+    let mut estimated_tail_tokens = cached.as_ref().map(|c| c.estimated_tail_tokens).unwrap_or(0);
+    let start_offset = cached.as_ref().map(|c| c.offset).unwrap_or(0);
+
+    file.seek(SeekFrom::Start(start_offset))?;
+    let mut reader = BufReader::new(file);
+
+    // Track how far we've read separately from how far we've *committed*:
+    // a line with no trailing `\n` yet is still being written by the
+    // transcript writer, so we must not advance past it or we'd skip the
+    // record for good once it's finally flushed complete.
+    let mut read_offset = start_offset;
+    let mut committed_offset = start_offset;
+    let mut line_buf = Vec::new();
+
+    loop {
+        line_buf.clear();
+        let bytes_read = reader.read_until(b'\n', &mut line_buf)?;
+        if bytes_read == 0 {
+            break;
+        }
+        read_offset += bytes_read as u64;
 
-    let mut current_token_usage = 0u64;
+        if line_buf.last() != Some(&b'\n') {
+            // Partial line at EOF; leave it for the next invocation.
+            break;
+        }
+        committed_offset = read_offset;
 
-    for line in reader.lines() {
-        let line = line?;
-        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(&line) {
+        let line = String::from_utf8_lossy(&line_buf);
+        let line = line.trim_end_matches(['\n', '\r']);
+        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) {
             if let Some(message) = entry.message {
-                if let Some(usage) = message.usage {
-                    let create_tokens = usage.cache_creation_input_tokens;
-                    let read_tokens = usage.cache_read_input_tokens;
-                    let input_tokens = create_tokens + read_tokens;
-
-                    if input_tokens > 0 {
-                        current_token_usage = input_tokens;
+                match &message.usage {
+                    Some(usage) => {
+                        let create_tokens = usage.cache_creation_input_tokens;
+                        let read_tokens = usage.cache_read_input_tokens;
+                        let input_tokens = create_tokens + read_tokens;
+
+                        if input_tokens > 0 {
+                            current_token_usage = input_tokens;
+                            estimated_tail_tokens = 0;
+                        }
+
+                        totals += pricing::TokenTotals {
+                            input_tokens: usage.input_tokens,
+                            output_tokens: usage.output_tokens,
+                            cache_creation_input_tokens: create_tokens,
+                            cache_read_input_tokens: read_tokens,
+                        };
+                    }
+                    None if config.estimate_missing_tokens => {
+                        if let Some(content) = &message.content {
+                            let text = estimator::extract_text(content);
+                            estimated_tail_tokens += estimator::estimate_tokens(&text);
+                        }
                     }
+                    None => {}
                 }
             }
         }
     }
 
-    let ratio = current_token_usage as f64 / TOKEN_LIMIT;
-    let color = if ratio > 0.7 {
-        "red"
-    } else if ratio > 0.5 {
-        "orange"
-    } else if ratio > 0.3 {
-        "yellow"
+    let cache = Cache {
+        offset: committed_offset,
+        len: file_len,
+        current_token_usage,
+        totals,
+        estimated_tail_tokens,
+    };
+    // Best-effort: a cache write failure shouldn't stop us from reporting usage.
+    let _ = cache.save(&input.transcript_path);
+
+    let pricing_table = pricing::load_table();
+    let cost = pricing_table
+        .get(&input.model.display_name)
+        .map(|rates| pricing::estimate_cost(totals, rates));
+
+    let context_window_table = context_window::load_table();
+    let token_limit = context_window::window_for(&context_window_table, &input.model.display_name);
+
+    // Estimated tokens cover entries since the last real usage report, so
+    // combining them gives a fill estimate that degrades gracefully instead
+    // of undercounting when a turn hasn't reported usage yet. Gated on the
+    // config flag so a stale estimate left over from a previous run (while
+    // estimation was enabled) doesn't keep inflating the reported usage
+    // after the user turns it back off.
+    let estimated_token_usage = if config.estimate_missing_tokens {
+        current_token_usage + estimated_tail_tokens
     } else {
-        "grey69"
+        current_token_usage
     };
 
-    let percentage = (100.0 * current_token_usage as f64 / TOKEN_LIMIT) as i32;
+    let ratio = estimated_token_usage as f64 / token_limit;
+    let percentage = (100.0 * estimated_token_usage as f64 / token_limit) as i32;
 
     let percentage_str = format!("{}%", percentage);
-    let colored_percentage = match color {
-        "red" => percentage_str.red().to_string(),
-        "orange" => percentage_str.truecolor(255, 165, 0).to_string(),
-        "yellow" => percentage_str.yellow().to_string(),
-        _ => percentage_str.truecolor(175, 175, 175).to_string(),
-    };
+    let percentage_color = config.theme.color_for(ratio);
+    let colored_percentage = color::paint_rgb(
+        color_mode,
+        config.theme.palette,
+        &percentage_str,
+        percentage_color,
+    );
 
     let path = PathBuf::from(&input.cwd);
     let colored_path = if let Some(filename) = path.file_name() {
         let parent = path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+        let filename = filename.to_string_lossy().to_string();
         if !parent.is_empty() {
             format!(
                 "{}/{}",
-                parent.truecolor(175, 175, 175),
-                filename.to_string_lossy().cyan()
+                color::paint_rgb(color_mode, config.theme.palette, &parent, [175, 175, 175]),
+                paint(color_mode, &filename, |s| s.cyan().to_string())
             )
         } else {
-            filename.to_string_lossy().cyan().to_string()
+            paint(color_mode, &filename, |s| s.cyan().to_string())
         }
     } else {
-        input.cwd.truecolor(175, 175, 175).to_string()
+        color::paint_rgb(color_mode, config.theme.palette, &input.cwd, [175, 175, 175])
     };
 
-    println!(
-        "{} {} {}",
-        colored_path,
-        colored_percentage,
-        input.model.display_name.blue()
-    );
+    let colored_cost = cost.map(|c| {
+        let cost_str = format!("${:.2}", c);
+        paint(color_mode, &cost_str, |s| s.green().to_string())
+    });
+
+    let colored_model = paint(color_mode, &input.model.display_name, |s| s.blue().to_string());
+
+    let colored_git_branch = git::current_branch(&path)
+        .map(|branch| paint(color_mode, &branch, |s| s.magenta().to_string()));
+
+    let rendered_segments: Vec<String> = config
+        .segments
+        .iter()
+        .filter_map(|segment| match segment {
+            config::Segment::Path => Some(colored_path.clone()),
+            config::Segment::Percentage => Some(colored_percentage.clone()),
+            config::Segment::Model => Some(colored_model.clone()),
+            config::Segment::Cost => colored_cost.clone(),
+            config::Segment::GitBranch => colored_git_branch.clone(),
+        })
+        .collect();
+
+    println!("{}", rendered_segments.join(" "));
 
     Ok(())
 }
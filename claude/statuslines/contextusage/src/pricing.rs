@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+/// Per-million-token rates, in USD, for a single model.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Rates {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+/// Token counts accumulated across a transcript, one field per billing
+/// category.
+#[derive(Default, Clone, Copy, Serialize, Deserialize)]
+pub struct TokenTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+impl std::ops::AddAssign for TokenTotals {
+    fn add_assign(&mut self, other: TokenTotals) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+    }
+}
+
+/// Built-in per-million-token rates, keyed by `Model::display_name`. Users
+/// can override or extend this via `CLAUDE_STATUSLINE_PRICING_OVERRIDE`,
+/// a path to a JSON file of the same shape.
+fn default_rates() -> HashMap<&'static str, Rates> {
+    HashMap::from([
+        (
+            "Claude Opus 4.5",
+            Rates {
+                input: 15.0,
+                output: 75.0,
+                cache_write: 18.75,
+                cache_read: 1.5,
+            },
+        ),
+        (
+            "Claude Sonnet 4.5",
+            Rates {
+                input: 3.0,
+                output: 15.0,
+                cache_write: 3.75,
+                cache_read: 0.3,
+            },
+        ),
+        (
+            "Claude Haiku 4.5",
+            Rates {
+                input: 1.0,
+                output: 5.0,
+                cache_write: 1.25,
+                cache_read: 0.1,
+            },
+        ),
+    ])
+}
+
+/// Loads the built-in pricing table, merged with any overrides found at
+/// `CLAUDE_STATUSLINE_PRICING_OVERRIDE` (a JSON object of `display_name` ->
+/// `Rates`). Overrides are best-effort: a missing or unparsable file is
+/// silently ignored and the defaults are used as-is.
+pub fn load_table() -> HashMap<String, Rates> {
+    let mut table: HashMap<String, Rates> = default_rates()
+        .into_iter()
+        .map(|(name, rates)| (name.to_string(), rates))
+        .collect();
+
+    if let Ok(path) = env::var("CLAUDE_STATUSLINE_PRICING_OVERRIDE") {
+        if let Ok(data) = fs::read_to_string(path) {
+            if let Ok(overrides) = serde_json::from_str::<HashMap<String, Rates>>(&data) {
+                table.extend(overrides);
+            }
+        }
+    }
+
+    table
+}
+
+/// Computes the total USD cost of `totals` under `rates`, treating each
+/// category as priced per million tokens.
+pub fn estimate_cost(totals: TokenTotals, rates: &Rates) -> f64 {
+    totals.input_tokens as f64 / 1_000_000.0 * rates.input
+        + totals.output_tokens as f64 / 1_000_000.0 * rates.output
+        + totals.cache_creation_input_tokens as f64 / 1_000_000.0 * rates.cache_write
+        + totals.cache_read_input_tokens as f64 / 1_000_000.0 * rates.cache_read
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_cost_sums_each_category_at_its_own_rate() {
+        let totals = TokenTotals {
+            input_tokens: 1_000_000,
+            output_tokens: 500_000,
+            cache_creation_input_tokens: 2_000_000,
+            cache_read_input_tokens: 4_000_000,
+        };
+        let rates = Rates {
+            input: 3.0,
+            output: 15.0,
+            cache_write: 3.75,
+            cache_read: 0.3,
+        };
+
+        let cost = estimate_cost(totals, &rates);
+
+        assert_eq!(cost, 3.0 + 7.5 + 7.5 + 1.2);
+    }
+
+    #[test]
+    fn estimate_cost_of_empty_totals_is_zero() {
+        let rates = Rates {
+            input: 3.0,
+            output: 15.0,
+            cache_write: 3.75,
+            cache_read: 0.3,
+        };
+        assert_eq!(estimate_cost(TokenTotals::default(), &rates), 0.0);
+    }
+}